@@ -1,14 +1,84 @@
 use std::env;
 use std::path::{Path, PathBuf};
 
+/// Minimum `everest-framework` version we require when discovering it via pkg-config.
+const MIN_FRAMEWORK_VERSION: &str = "0.1";
+
 struct Libraries {
     everestrs_sys: PathBuf,
     framework: PathBuf,
 }
 
-fn find_everest_workspace_root() -> PathBuf {
+/// Whether `libframework` (and the boost libs) should be linked statically or dynamically.
+///
+/// Follows the common `FOO_STATIC`/`FOO_DYNAMIC` convention used by other `-sys` crates.
+/// Defaults to dynamic linking, matching the previous, non-configurable behavior.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LinkMode {
+    Static,
+    Dynamic,
+}
+
+fn link_mode() -> LinkMode {
+    let want_static = env::var("EVEREST_FRAMEWORK_STATIC").is_ok();
+    let want_dynamic = env::var("EVEREST_FRAMEWORK_DYNAMIC").is_ok();
+    if want_static && want_dynamic {
+        panic!("EVEREST_FRAMEWORK_STATIC and EVEREST_FRAMEWORK_DYNAMIC are mutually exclusive.");
+    }
+    if want_static {
+        LinkMode::Static
+    } else {
+        LinkMode::Dynamic
+    }
+}
+
+/// The `libframework` file name to look for given the requested `LinkMode`.
+fn framework_file_name(mode: LinkMode) -> &'static str {
+    match mode {
+        LinkMode::Static => "libframework.a",
+        LinkMode::Dynamic => "libframework.so",
+    }
+}
+
+fn lib_kind_from_mode(mode: LinkMode) -> &'static str {
+    match mode {
+        LinkMode::Static => "static",
+        LinkMode::Dynamic => "dylib",
+    }
+}
+
+/// The boost libraries to link against, e.g. `["boost_log", "boost_log_setup"]`.
+///
+/// Configurable via a comma-separated `EVEREST_BOOST_LIBS` (component names, without the `boost_`
+/// prefix) so downstream integrators that need extra components (thread, filesystem, system) or
+/// a differently configured boost don't have to patch `build.rs`.
+fn boost_components() -> Vec<String> {
+    env::var("EVEREST_BOOST_LIBS")
+        .unwrap_or_else(|_| "log,log_setup".to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|component| !component.is_empty())
+        .map(|component| format!("boost_{component}"))
+        .collect()
+}
+
+/// Returns the directories to probe for libraries in, in search order: the target-specific
+/// subdirectory first (mirroring the `lib/<target-triple>/` layout multi-arch builds commonly
+/// use), then the generic directory, so cross-compiling doesn't silently pick up host libraries.
+fn candidate_lib_dirs(lib_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(target) = env::var("TARGET") {
+        dirs.push(lib_dir.join(target));
+    }
+    dirs.push(lib_dir.to_path_buf());
+    dirs
+}
+
+/// Same as `find_everest_workspace_root`, but returns `None` instead of panicking when no
+/// workspace can be found, for callers that have another fallback (e.g. `EVEREST_LIB_DIR`).
+fn try_find_everest_workspace_root() -> Option<PathBuf> {
     if let Ok(everest_framework_dir) = env::var("EVEREST_RS_FRAMEWORK_SOURCE_LOCATION") {
-        return PathBuf::from(everest_framework_dir);
+        return Some(PathBuf::from(everest_framework_dir));
     }
 
     let mut cur_dir =
@@ -19,55 +89,82 @@ fn find_everest_workspace_root() -> PathBuf {
     while cur_dir.parent().is_some() {
         cur_dir = cur_dir.parent().unwrap().to_path_buf();
         if cur_dir.join("everest-framework").is_dir() {
-            return cur_dir;
+            return Some(cur_dir);
+        }
+    }
+    None
+}
+
+fn find_everest_workspace_root() -> PathBuf {
+    try_find_everest_workspace_root().expect("everstrs is not build within an EVerest workspace.")
+}
+
+/// Locates `libeverestrs_sys.a`, looking directly in `EVEREST_LIB_DIR` first and then in the
+/// workspace's `everest-framework/build/everestrs`. Unlike the heuristic discovery path, it does
+/// not look in a target-specific subdirectory, `EVEREST_RS_FRAMEWORK_BINARY_LOCATION` or
+/// `everest-core/build/dist/lib`. Returns `None` instead of panicking so callers (like the
+/// pkg-config path) can fall back to the heuristics themselves.
+fn find_everestrs_sys() -> Option<PathBuf> {
+    if let Ok(lib_dir) = env::var("EVEREST_LIB_DIR") {
+        let p = PathBuf::from(lib_dir).join("libeverestrs_sys.a");
+        if p.exists() {
+            return Some(p);
         }
     }
-    panic!("everstrs is not build within an EVerest workspace.");
+
+    let p = try_find_everest_workspace_root()?
+        .join("everest-framework/build/everestrs/libeverestrs_sys.a");
+    p.exists().then_some(p)
 }
 
 /// Returns the Libraries path if this is a standalone build of everest-framework or None if it is
 /// not.
-fn find_libs_in_everest_framework(root: &Path) -> Option<Libraries> {
-    let (everestrs_sys, framework) =
+fn find_libs_in_everest_framework(root: &Path, mode: LinkMode) -> Option<Libraries> {
+    let framework_name = framework_file_name(mode);
+    let (everestrs_sys_dir, lib_dir) =
         if let Ok(everest_framework_dir) = env::var("EVEREST_RS_FRAMEWORK_BINARY_LOCATION") {
             let everest_framework_path = PathBuf::from(everest_framework_dir);
             (
-                everest_framework_path.join("everestrs/libeverestrs_sys.a"),
-                everest_framework_path.join("lib/libframework.so"),
+                everest_framework_path.join("everestrs"),
+                everest_framework_path.join("lib"),
             )
         } else {
             (
-                root.join("everest-framework/build/everestrs/libeverestrs_sys.a"),
-                root.join("everest-framework/build/lib/libframework.so"),
+                root.join("everest-framework/build/everestrs"),
+                root.join("everest-framework/build/lib"),
             )
         };
-    if everestrs_sys.exists() && framework.exists() {
-        Some(Libraries {
-            everestrs_sys,
-            framework,
-        })
-    } else {
-        None
+    let everestrs_sys = everestrs_sys_dir.join("libeverestrs_sys.a");
+    for lib_dir in candidate_lib_dirs(&lib_dir) {
+        let framework = lib_dir.join(framework_name);
+        if everestrs_sys.exists() && framework.exists() {
+            return Some(Libraries {
+                everestrs_sys,
+                framework,
+            });
+        }
     }
+    None
 }
 
-fn find_libs_in_dir(lib_dir: &Path) -> Option<Libraries> {
-    let everestrs_sys = lib_dir.join("libeverestrs_sys.a");
-    let framework = lib_dir.join("libframework.so");
-    if everestrs_sys.exists() && framework.exists() {
-        Some(Libraries {
-            everestrs_sys,
-            framework,
-        })
-    } else {
-        None
+fn find_libs_in_dir(lib_dir: &Path, mode: LinkMode) -> Option<Libraries> {
+    for lib_dir in candidate_lib_dirs(lib_dir) {
+        let everestrs_sys = lib_dir.join("libeverestrs_sys.a");
+        let framework = lib_dir.join(framework_file_name(mode));
+        if everestrs_sys.exists() && framework.exists() {
+            return Some(Libraries {
+                everestrs_sys,
+                framework,
+            });
+        }
     }
+    None
 }
 
 /// Returns the Libraries path if this is an EVerest workspace where make install was run in
 /// everest-core/build or None if not.
-fn find_libs_in_everest_core_build_dist(root: &Path) -> Option<Libraries> {
-    find_libs_in_dir(&root.join("everest-core/build/dist/lib"))
+fn find_libs_in_everest_core_build_dist(root: &Path, mode: LinkMode) -> Option<Libraries> {
+    find_libs_in_dir(&root.join("everest-core/build/dist/lib"), mode)
 }
 
 /// Takes a path to a library like `libframework.so` and returns the name for the linker, aka
@@ -81,12 +178,24 @@ fn libname_from_path(p: &Path) -> String {
         .to_string()
 }
 
+/// Whether `p` should be linked statically or dynamically, derived from its extension.
+fn lib_kind_from_path(p: &Path) -> &'static str {
+    match p.extension().and_then(|os_str| os_str.to_str()) {
+        Some("a") => "static",
+        _ => "dylib",
+    }
+}
+
 fn print_link_options(p: &Path) {
     println!(
         "cargo:rustc-link-search=native={}",
         p.parent().unwrap().to_string_lossy()
     );
-    println!("cargo:rustc-link-lib={}", libname_from_path(p));
+    println!(
+        "cargo:rustc-link-lib={}={}",
+        lib_kind_from_path(p),
+        libname_from_path(p)
+    );
     // If the c++ libraries are build with `-fprofile-arcs -ftest-coverage`
     // compiler flags we've to link against the `gcov` lib as well.
     if env::var("CARGO_FEATURE_LINK_GCOV").is_ok() {
@@ -94,16 +203,236 @@ fn print_link_options(p: &Path) {
     }
 }
 
-fn find_libs_in_everest_workspace() -> Option<Libraries> {
+/// The ELF `(EI_CLASS, e_machine)` pair expected for a given Rust target triple, or `None` if we
+/// don't know how to validate it (in which case we skip validation rather than false-positive).
+fn expected_elf_header(target: &str) -> Option<(u8, u16)> {
+    if target.starts_with("x86_64") {
+        Some((2, 0x3E))
+    } else if target.starts_with("aarch64") {
+        Some((2, 0xB7))
+    } else if target.starts_with("i686") || target.starts_with("i586") {
+        Some((1, 0x03))
+    } else if target.starts_with("arm") {
+        Some((1, 0x28))
+    } else {
+        None
+    }
+}
+
+/// The Mach-O `cputype` expected for a given Rust target triple, or `None` if unknown.
+fn expected_macho_cputype(target: &str) -> Option<u32> {
+    if target.starts_with("x86_64") {
+        Some(0x0100_0007)
+    } else if target.starts_with("aarch64") {
+        Some(0x0100_000C)
+    } else {
+        None
+    }
+}
+
+/// Reads the object header of `path` and panics with a precise message if its architecture
+/// doesn't match `target`, instead of letting the linker fail with a cryptic error later.
+fn validate_framework_arch(path: &Path, target: &str) {
+    use std::fs::File;
+    use std::io::Read;
+
+    let mut header = [0u8; 20];
+    let mut file = File::open(path)
+        .unwrap_or_else(|e| panic!("could not open {} for arch validation: {e}", path.display()));
+    let read = file
+        .read(&mut header)
+        .unwrap_or_else(|e| panic!("could not read {} for arch validation: {e}", path.display()));
+    if read < 20 {
+        return;
+    }
+
+    if header[0..4] == [0x7F, b'E', b'L', b'F'] {
+        let Some((expected_class, expected_machine)) = expected_elf_header(target) else {
+            return;
+        };
+        let class = header[4];
+        // EI_DATA (offset 5): 1 = little-endian (ELFDATA2LSB), 2 = big-endian (ELFDATA2MSB).
+        // e_machine is encoded in that same byte order, so it must be decoded accordingly.
+        let machine = if header[5] == 2 {
+            u16::from_be_bytes([header[18], header[19]])
+        } else {
+            u16::from_le_bytes([header[18], header[19]])
+        };
+        if class != expected_class || machine != expected_machine {
+            panic!(
+                "{} does not match TARGET {target}: expected ELF class {expected_class} / \
+                 e_machine {expected_machine:#x}, found class {class} / e_machine {machine:#x}",
+                path.display()
+            );
+        }
+    } else if header[0..4] == [0xCF, 0xFA, 0xED, 0xFE] || header[0..4] == [0xFE, 0xED, 0xFA, 0xCF] {
+        let Some(expected_cputype) = expected_macho_cputype(target) else {
+            return;
+        };
+        let le = header[0..4] == [0xCF, 0xFA, 0xED, 0xFE];
+        let cputype_bytes = [header[4], header[5], header[6], header[7]];
+        let cputype = if le {
+            u32::from_le_bytes(cputype_bytes)
+        } else {
+            u32::from_be_bytes(cputype_bytes)
+        };
+        if cputype != expected_cputype {
+            panic!(
+                "{} does not match TARGET {target}: expected Mach-O cputype {expected_cputype:#x}, \
+                 found {cputype:#x}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Emits `cargo:root`, `cargo:lib_dir` and `cargo:include` so that any downstream crate with
+/// `links = "everest-framework"` can read them back via `DEP_EVEREST_FRAMEWORK_ROOT` /
+/// `DEP_EVEREST_FRAMEWORK_LIB_DIR` / `DEP_EVEREST_FRAMEWORK_INCLUDE` in its own build script,
+/// instead of re-running this crate's discovery heuristics itself.
+fn emit_downstream_metadata(libs: &Libraries, include_dir: Option<&Path>) {
+    let lib_dir = libs
+        .framework
+        .parent()
+        .expect("framework library path must have a parent directory");
+
+    // `candidate_lib_dirs` prefers `lib_dir/<target-triple>/` when it matched; strip that
+    // component before deriving `root`, otherwise it ends up one level too deep (e.g.
+    // `.../lib` instead of the real install prefix).
+    let generic_lib_dir = match env::var("TARGET") {
+        Ok(target)
+            if lib_dir
+                .file_name()
+                .is_some_and(|name| name == target.as_str()) =>
+        {
+            lib_dir.parent().unwrap_or(lib_dir)
+        }
+        _ => lib_dir,
+    };
+    let root = generic_lib_dir.parent().unwrap_or(generic_lib_dir);
+    let include_dir = include_dir
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| root.join("include"));
+
+    println!("cargo:root={}", root.display());
+    println!("cargo:lib_dir={}", lib_dir.display());
+    println!("cargo:include={}", include_dir.display());
+}
+
+fn find_libs_in_everest_workspace(mode: LinkMode) -> Option<Libraries> {
     let root = find_everest_workspace_root();
-    let libs = find_libs_in_everest_core_build_dist(&root);
+    let libs = find_libs_in_everest_core_build_dist(&root, mode);
     if libs.is_some() {
         return libs;
     }
-    find_libs_in_everest_framework(&root)
+    find_libs_in_everest_framework(&root, mode)
+}
+
+/// Emits the `cargo:rustc-link-*` directives described by a probed pkg-config `Library`.
+///
+/// We disable `pkg_config`'s own `cargo_metadata` so we stay in full control of what gets
+/// printed, the same way `print_link_options` does for the heuristic search path (e.g. the
+/// `gcov` special case below). `mode` tags each `lib.libs` entry with `static=`/`dylib=` the same
+/// way `lib_kind_from_path` does for the heuristic path, since `pkg_config::Config::statik` only
+/// controls which libs/flags pkg-config enumerates, not the kind rustc links them as.
+#[cfg(feature = "pkg-config")]
+fn emit_pkg_config_link_options(lib: &pkg_config::Library, mode: LinkMode) {
+    for path in &lib.link_paths {
+        println!("cargo:rustc-link-search=native={}", path.display());
+    }
+    for path in &lib.framework_paths {
+        println!("cargo:rustc-link-search=framework={}", path.display());
+    }
+    for name in &lib.libs {
+        println!("cargo:rustc-link-lib={}={name}", lib_kind_from_mode(mode));
+    }
+    for name in &lib.frameworks {
+        println!("cargo:rustc-link-lib=framework={name}");
+    }
+    for path in &lib.link_files {
+        println!("cargo:rustc-link-lib=static={}", libname_from_path(path));
+    }
+    if env::var("CARGO_FEATURE_LINK_GCOV").is_ok() {
+        println!("cargo:rustc-link-lib=gcov");
+    }
+}
+
+/// Tries to discover `everest-framework` and the boost logging libraries via pkg-config instead
+/// of the upward directory traversal in `find_libs_in_everest_workspace`. Returns `None` if no
+/// `.pc` file for `everest-framework` can be found, in which case the caller should fall back to
+/// the heuristics.
+///
+/// `libeverestrs_sys.a` is a build artifact private to this crate and is never installed
+/// system-wide, so it is still located the old way even when the framework itself is found here.
+///
+/// Honors `mode` the same way the heuristic path does: `pkg_config::Config::statik` asks
+/// pkg-config for the static linker flags (e.g. `.a` / `--static` libs) instead of the default
+/// dynamic ones, and the emitted directives are tagged `static=`/`dylib=` accordingly. Also runs
+/// the same `TARGET` architecture validation as the heuristic path before returning, since a
+/// misconfigured cross `PKG_CONFIG_SYSROOT_DIR`/`.pc` file can resolve to a host-architecture
+/// library just as easily as the directory traversal can.
+#[cfg(feature = "pkg-config")]
+fn find_libs_via_pkg_config(mode: LinkMode) -> Option<Libraries> {
+    let framework_lib = pkg_config::Config::new()
+        .atleast_version(MIN_FRAMEWORK_VERSION)
+        .statik(mode == LinkMode::Static)
+        .cargo_metadata(false)
+        .probe("everest-framework")
+        .ok()?;
+
+    let framework = framework_lib
+        .link_paths
+        .first()?
+        .join(framework_file_name(mode));
+    if !framework.exists() {
+        return None;
+    }
+
+    let everestrs_sys = find_everestrs_sys()?;
+
+    if mode == LinkMode::Dynamic {
+        if let Ok(target) = env::var("TARGET") {
+            validate_framework_arch(&framework, &target);
+        }
+    }
+
+    emit_pkg_config_link_options(&framework_lib, mode);
+
+    for component in boost_components() {
+        if let Ok(boost_lib) = pkg_config::Config::new()
+            .statik(mode == LinkMode::Static)
+            .cargo_metadata(false)
+            .probe(&component)
+        {
+            emit_pkg_config_link_options(&boost_lib, mode);
+        } else {
+            // Not every distro ships a `.pc` file for boost; fall back to the plain library
+            // name and hope it's on the linker's default search path.
+            println!(
+                "cargo:rustc-link-lib={}={component}",
+                lib_kind_from_mode(mode)
+            );
+        }
+    }
+
+    let libs = Libraries {
+        everestrs_sys,
+        framework,
+    };
+    let include_dir = framework_lib.include_paths.first().map(PathBuf::as_path);
+    emit_downstream_metadata(&libs, include_dir);
+    Some(libs)
 }
 
 fn main() {
+    // Building docs (docs.rs, or `cargo check`/`cargo doc` in an environment without a compiled
+    // EVerest workspace) shouldn't require any native library to be present. Skip discovery and
+    // linking entirely so the crate's public API and rustdoc still build.
+    if env::var("DOCS_RS").is_ok() || env::var("CARGO_FEATURE_DOCS_ONLY").is_ok() {
+        println!("cargo:warning=Skipping native library discovery for a docs-only build");
+        return;
+    }
+
     // See https://doc.rust-lang.org/cargo/reference/features.html#build-scripts
     // for details.
     if env::var("CARGO_FEATURE_BUILD_BAZEL").is_ok() {
@@ -111,17 +440,39 @@ fn main() {
         return;
     }
 
+    let mode = link_mode();
+
+    #[cfg(feature = "pkg-config")]
+    if env::var("CARGO_FEATURE_PKG_CONFIG").is_ok() {
+        if let Some(libs) = find_libs_via_pkg_config(mode) {
+            print_link_options(&libs.everestrs_sys);
+            return;
+        }
+    }
+
     let libs = match env::var("EVEREST_LIB_DIR") {
-        Ok(p) => find_libs_in_dir(&Path::new(&p)),
-        Err(_) => find_libs_in_everest_workspace(),
+        Ok(p) => find_libs_in_dir(&Path::new(&p), mode),
+        Err(_) => find_libs_in_everest_workspace(mode),
     };
 
     let libs = libs
         .expect("Could not find libframework.so and libeverestrs_sys. Either set EVEREST_LIB_DIR to a path
         that contains them or run the build again with everestrs being inside an everest workspace.");
 
+    emit_downstream_metadata(&libs, None);
+
+    if mode == LinkMode::Dynamic {
+        if let Ok(target) = env::var("TARGET") {
+            validate_framework_arch(&libs.framework, &target);
+        }
+    }
+
     print_link_options(&libs.everestrs_sys);
     print_link_options(&libs.framework);
-    println!("cargo:rustc-link-lib=boost_log");
-    println!("cargo:rustc-link-lib=boost_log_setup");
+    for component in boost_components() {
+        println!(
+            "cargo:rustc-link-lib={}={component}",
+            lib_kind_from_mode(mode)
+        );
+    }
 }